@@ -1,7 +1,11 @@
-use std::collections::VecDeque;
+use std::{any::TypeId, collections::HashMap};
 
 use bevy::{
-    ecs::{entity::MapEntities, reflect::ReflectMapEntitiesResource},
+    core::FrameCount,
+    ecs::{
+        entity::MapEntities,
+        reflect::{ReflectComponent, ReflectMapEntities, ReflectMapEntitiesResource},
+    },
     prelude::*,
     reflect::FromType,
 };
@@ -9,6 +13,17 @@ use bevy::{
 pub trait Action: Component + Placeholder {
     fn apply(&mut self, world: &mut World);
     fn undo(&mut self, world: &mut World);
+
+    /// Fold `next` - the action that has just been applied - into `self` in place, returning whether the
+    /// merge happened. When it does, [`PerformAction`] discards `next` instead of spawning a
+    /// new history entry, so `self`'s existing entry comes to represent both actions combined.
+    ///
+    /// Defaults to `false` (never merge). Override this for actions driven by continuous input
+    /// (holding a movement key, dragging) so a whole gesture collapses into one undo step.
+    fn merge(&mut self, next: &Self) -> bool {
+        let _ = next;
+        false
+    }
 }
 
 /// Types that need a [`Default`] like value when there's no sensible default representation of said type.
@@ -48,25 +63,169 @@ impl<T: Action> Command for PerformAction<T> {
             }
         }
 
+        // try to coalesce with the most recent entry instead of spawning a new one, as long as
+        // we're not mid-transaction, we're within the merge window, and it's the same action type
+        let merge_target = (!world.contains_resource::<TransactionBuffer>())
+            .then(|| {
+                let frame = world.resource::<FrameCount>().0;
+                let history = world.resource::<History>();
+                let candidate = history
+                    .merge_window
+                    .zip(history.last_push_frame)
+                    .filter(|&(window, last_frame)| frame.wrapping_sub(last_frame) <= window)
+                    .and_then(|_| history.last())
+                    // a node with children is a branch point another entry already sits on top
+                    // of (e.g. the node `Undo` just stepped back to) - merging into it in place
+                    // would silently rewrite history instead of starting a fresh gesture
+                    .filter(|&candidate| history.is_leaf(candidate))?;
+                world
+                    .get::<HistoryItem>(candidate)
+                    .filter(|item| item.type_id == TypeId::of::<T>())
+                    .map(|_| candidate)
+            })
+            .flatten();
+
         self.action.apply(world);
+
+        if let Some(entity) = merge_target {
+            let merged = world
+                .get_mut::<T>(entity)
+                .is_some_and(|mut last_action| last_action.merge(&self.action));
+
+            if merged {
+                let frame = world.resource::<FrameCount>().0;
+                world.resource_mut::<History>().last_push_frame = Some(frame);
+                return;
+            }
+        }
+
         let entity = world.spawn((self.action, HistoryItem::new::<T>())).id();
-        let future = world.resource_mut::<History>().push(entity);
-        for entity in future {
-            world.despawn(entity);
+
+        // while a transaction is open, collect into it instead of pushing straight to history
+        if let Some(mut batch) = world.get_resource_mut::<TransactionBuffer>() {
+            batch.children.push(entity);
+            return;
+        }
+
+        let evicted = world.resource_mut::<History>().push(entity);
+        for entity in evicted {
+            despawn_entry(world, entity);
+        }
+
+        let frame = world.resource::<FrameCount>().0;
+        world.resource_mut::<History>().last_push_frame = Some(frame);
+    }
+}
+
+/// Despawn a history entry's backing entity, along with any sub-action entities bundled into it
+/// by [`HistoryItem::group`] - those aren't pushed to [`History`] themselves, so nothing else
+/// ever despawns them once their wrapping group entry is evicted.
+fn despawn_entry(world: &mut World, entity: Entity) {
+    let children = world
+        .get::<HistoryItem>(entity)
+        .map(|item| item.children.clone())
+        .unwrap_or_default();
+    for child in children {
+        despawn_entry(world, child);
+    }
+    world.despawn(entity);
+}
+
+/// Actions performed between [`Transaction::Begin`] and [`Transaction::Commit`] collapse into a
+/// single [`HistoryItem`], so one [`Undo`]/[`Redo`] reverts/replays all of them together.
+///
+/// Use this for a drag that emits many `MoveEntity`s, or "add level + move player" as one
+/// atomic editor operation.
+pub enum Transaction {
+    Begin,
+    Commit,
+}
+
+impl Command for Transaction {
+    fn apply(self, world: &mut World) {
+        match self {
+            Self::Begin => {
+                world.init_resource::<TransactionBuffer>();
+            }
+            Self::Commit => {
+                let Some(batch) = world.remove_resource::<TransactionBuffer>() else {
+                    warn!("Transaction::Commit with no open transaction");
+                    return;
+                };
+
+                if batch.children.is_empty() {
+                    return;
+                }
+
+                let entity = world
+                    .spawn((
+                        HistoryItem::group(batch.children.clone()),
+                        HistoryGroup(batch.children),
+                    ))
+                    .id();
+                let evicted = world.resource_mut::<History>().push(entity);
+                for entity in evicted {
+                    despawn_entry(world, entity);
+                }
+            }
+        }
+    }
+}
+
+/// Holds the actions performed so far in an open [`Transaction`].
+#[derive(Resource, Default)]
+struct TransactionBuffer {
+    children: Vec<Entity>,
+}
+
+/// Reflectable record of a [`Transaction::Commit`] group's sub-action entities.
+///
+/// [`HistoryItem::group`]'s `fn` pointers aren't `Reflect`, so without this a group entity
+/// carries nothing the scene filter can serialize and gets dropped by `remove_empty_entities` on
+/// save, orphaning the [`History`] node that still points at it. This component rides alongside
+/// `HistoryItem` so the group entity - and the list of entities it undoes/redoes together -
+/// survives a save/load round trip; pair with [`rebuild_groups`] to restore the `HistoryItem`
+/// that load can't bring back on its own.
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, MapEntities)]
+pub struct HistoryGroup(pub Vec<Entity>);
+
+impl MapEntities for HistoryGroup {
+    fn map_entities<M: EntityMapper>(&mut self, mapper: &mut M) {
+        for entity in &mut self.0 {
+            *entity = mapper.map_entity(*entity);
         }
     }
 }
 
+/// Rebuild the [`HistoryItem`] for any loaded [`HistoryGroup`] that doesn't have one yet. Scene
+/// loading restores `HistoryGroup`, but not the non-`Reflect` `HistoryItem` spawned alongside it
+/// at save time - run this after spawning a loaded scene, before the first [`Undo`]/[`Redo`].
+pub fn rebuild_groups(
+    mut commands: Commands,
+    groups: Query<(Entity, &HistoryGroup), Without<HistoryItem>>,
+) {
+    for (entity, group) in &groups {
+        commands
+            .entity(entity)
+            .insert(HistoryItem::group(group.0.clone()));
+    }
+}
+
 /// Undo the last action
 pub struct Undo;
 
 impl Command for Undo {
     fn apply(self, world: &mut World) {
-        if let Some(entity) = world.resource_mut::<History>().back() {
-            let item = *world.get::<HistoryItem>(entity).unwrap();
-            item.undo(world, entity);
-        } else {
+        let Some(entity) = world.resource_mut::<History>().back() else {
             info!("end of history");
+            return;
+        };
+        match world.get::<HistoryItem>(entity).cloned() {
+            Some(item) => item.undo(world, entity),
+            // e.g. a `Transaction::Commit` group entity that wasn't rebuilt with
+            // `rebuild_groups` after a scene load
+            None => warn!("{entity:?} has no HistoryItem, skipping undo"),
         }
     }
 }
@@ -76,75 +235,326 @@ pub struct Redo;
 
 impl Command for Redo {
     fn apply(self, world: &mut World) {
-        if let Some(entity) = world.resource_mut::<History>().forward() {
-            let item = *world.get::<HistoryItem>(entity).unwrap();
-            item.redo(world, entity);
-        } else {
+        let Some(entity) = world.resource_mut::<History>().forward() else {
             info!("end of history");
+            return;
+        };
+        match world.get::<HistoryItem>(entity).cloned() {
+            Some(item) => item.redo(world, entity),
+            None => warn!("{entity:?} has no HistoryItem, skipping redo"),
         }
     }
 }
 
+/// A node in the [`History`] tree: its parent (for [`History::back`]) and ordered children (for
+/// [`History::forward`] and branch navigation).
+#[derive(Reflect, Debug, Clone, Default)]
+pub struct HistoryNode {
+    pub parent: Option<Entity>,
+    pub children: Vec<Entity>,
+    /// Which child [`History::forward`] goes to next. Tracks the most recently pushed or
+    /// switched-to child, so undoing and then performing a different action doesn't silently
+    /// change what redo does on branches you're not even looking at.
+    active_child: Option<Entity>,
+}
+
+/// Branching undo/redo history: performing a new action after undoing opens a sibling branch
+/// instead of discarding the undone one, so nothing performed is ever silently lost.
+///
+/// `Undo`/`Redo` walk the currently selected branch exactly like a linear history would;
+/// [`Self::children`], [`Self::switch_branch`] and [`Self::jump_to`] are there for editors that
+/// want to expose the rest of the tree.
 #[derive(Resource, Reflect, Default, Debug, Clone)]
 #[reflect(Resource, MapEntitiesResource, Default)]
 pub struct History {
-    pub items: VecDeque<Entity>,
-    pub index: usize,
+    nodes: HashMap<Entity, HistoryNode>,
+    /// Currently selected node; `None` means nothing has been performed yet, or everything has
+    /// been undone back to the start.
+    current: Option<Entity>,
+    /// Which root (parentless node) is "ahead" when `current` is `None`.
+    active_root: Option<Entity>,
+    /// Maximum number of entries to retain; `None` (the default) means unbounded. When a
+    /// [`push`](Self::push) would exceed this, the single oldest entry is evicted, wherever it
+    /// sits in the tree - [`current`] is the only entry this ever exempts.
+    ///
+    /// [`current`]: Self::current
+    pub max_len: Option<usize>,
+    /// Max gap, in frames, between pushes for [`Action::merge`] to be attempted; beyond it a
+    /// push always starts a fresh entry even if the action type matches. `None` (the default)
+    /// disables coalescing.
+    pub merge_window: Option<u32>,
+    last_push_frame: Option<u32>,
+    /// Every live entry, oldest first, so [`Self::evict_over_capacity`] can find the oldest one
+    /// without a tree walk.
+    order: Vec<Entity>,
 }
 
+/// Rough per-entry overhead (an [`Entity`] plus its spawned action and [`HistoryItem`]
+/// components) used to translate a byte budget into an entry count for
+/// [`History::with_byte_budget`]. This is a heuristic, not an exact accounting.
+const ESTIMATED_ENTRY_BYTES: usize = 128;
+
 impl MapEntities for History {
     fn map_entities<M: EntityMapper>(&mut self, mapper: &mut M) {
-        for e in self.items.iter_mut() {
-            *e = mapper.map_entity(*e);
+        self.nodes = self
+            .nodes
+            .drain()
+            .map(|(entity, mut node)| {
+                node.parent = node.parent.map(|e| mapper.map_entity(e));
+                node.active_child = node.active_child.map(|e| mapper.map_entity(e));
+                for child in &mut node.children {
+                    *child = mapper.map_entity(*child);
+                }
+                (mapper.map_entity(entity), node)
+            })
+            .collect();
+
+        self.current = self.current.map(|e| mapper.map_entity(e));
+        self.active_root = self.active_root.map(|e| mapper.map_entity(e));
+        for entity in &mut self.order {
+            *entity = mapper.map_entity(*entity);
         }
     }
 }
 
 impl History {
+    /// Build a history out of a single linear chain of already-performed entries, oldest first.
     pub fn new(past: impl IntoIterator<Item = Entity>) -> Self {
-        let actions = VecDeque::from_iter(past);
+        let mut history = Self::default();
+        for entity in past {
+            history.push(entity);
+        }
+        history
+    }
+
+    /// An empty history that evicts the oldest entries once it holds more than `max_len`.
+    pub fn with_capacity(max_len: usize) -> Self {
         Self {
-            index: actions.len(),
-            items: actions,
+            max_len: Some(max_len),
+            ..Default::default()
         }
     }
 
+    /// Like [`Self::with_capacity`], but sized from an approximate memory budget instead of an
+    /// entry count. Handy on memory constrained targets - wasm, say, where `save_scene`'s
+    /// filesystem path isn't even available - where a byte budget is easier to reason about
+    /// than an entry count.
+    pub fn with_byte_budget(bytes: usize) -> Self {
+        Self::with_capacity((bytes / ESTIMATED_ENTRY_BYTES).max(1))
+    }
+
+    /// Allow [`Action::merge`] to coalesce a push into the previous entry as long as it follows
+    /// within `frames` frames of it.
+    pub fn with_merge_window(mut self, frames: u32) -> Self {
+        self.merge_window = Some(frames);
+        self
+    }
+
+    /// The most recently performed, not-yet-undone entry, if any.
+    pub fn last(&self) -> Option<Entity> {
+        self.current
+    }
+
+    /// Whether `entity` is a leaf - has no children of its own. A node with children is a
+    /// branch point something else already sits on top of, so [`PerformAction`] only ever folds
+    /// a push into a leaf via [`Action::merge`].
+    fn is_leaf(&self, entity: Entity) -> bool {
+        self.nodes
+            .get(&entity)
+            .is_some_and(|node| node.children.is_empty())
+    }
+
     /// Go back one step in the history, returning the [`Entity`] of the [`HistoryItem`].
     pub fn back(&mut self) -> Option<Entity> {
-        if self.index > 0 {
-            self.index -= 1;
-            Some(self.items[self.index])
-        } else {
-            None
-        }
+        let current = self.current?;
+        self.current = self.nodes.get(&current).and_then(|node| node.parent);
+        Some(current)
     }
 
-    /// Go forward one step in the history, returning the [`Entity`] of the [`HistoryItem`].
+    /// Go forward one step in the history, following whichever branch is currently active,
+    /// returning the [`Entity`] of the [`HistoryItem`].
     pub fn forward(&mut self) -> Option<Entity> {
-        if self.index < self.items.len() {
-            let entity = self.items[self.index];
-            self.index += 1;
-            Some(entity)
-        } else {
-            None
-        }
+        let next = match self.current {
+            Some(current) => self.nodes.get(&current)?.active_child,
+            None => self.active_root,
+        }?;
+        self.current = Some(next);
+        Some(next)
     }
 
-    /// Push an item to the past, clearing the future history.
+    /// Push an item as a child of the currently selected node.
+    ///
+    /// `entity` may be a single action's history entity or a grouped one (see
+    /// [`HistoryItem::group`]) - either way it's just an [`Entity`] to the history.
+    ///
+    /// Unlike a linear undo stack, this never discards a branch you've undone away from - it
+    /// just becomes a sibling of the new entry. If this exceeds [`Self::max_len`], the oldest
+    /// entry overall is evicted instead, even one on the path to [`Self::last`].
     ///
     /// Note: the returned entities should be despawned.
     pub fn push(&mut self, entity: Entity) -> Vec<Entity> {
-        let removed = self.items.drain(self.index..).collect();
-        self.items.push_back(entity);
-        self.index += 1;
+        let parent = self.current;
+        self.nodes.insert(
+            entity,
+            HistoryNode {
+                parent,
+                ..Default::default()
+            },
+        );
+
+        match parent {
+            Some(parent) => {
+                let parent_node = self.nodes.get_mut(&parent).unwrap();
+                parent_node.children.push(entity);
+                parent_node.active_child = Some(entity);
+            }
+            None => self.active_root = Some(entity),
+        }
+
+        self.current = Some(entity);
+        self.order.push(entity);
+        self.evict_over_capacity()
+    }
+
+    /// The branches available from the currently selected node - what [`Self::switch_branch`]
+    /// can choose between.
+    pub fn children(&self) -> Vec<Entity> {
+        match self.current {
+            Some(current) => self
+                .nodes
+                .get(&current)
+                .map(|node| node.children.clone())
+                .unwrap_or_default(),
+            None => self
+                .nodes
+                .iter()
+                .filter(|(_, node)| node.parent.is_none())
+                .map(|(&entity, _)| entity)
+                .collect(),
+        }
+    }
+
+    /// Make `child` - one of [`Self::children`] - the branch [`Self::forward`] walks down from
+    /// here. Returns `false` if `child` isn't actually a child of the current node.
+    pub fn switch_branch(&mut self, child: Entity) -> bool {
+        match self.current {
+            Some(current) => {
+                let Some(node) = self.nodes.get(&current) else {
+                    return false;
+                };
+                if !node.children.contains(&child) {
+                    return false;
+                }
+                self.nodes.get_mut(&current).unwrap().active_child = Some(child);
+                true
+            }
+            None => {
+                let is_root = self
+                    .nodes
+                    .get(&child)
+                    .is_some_and(|node| node.parent.is_none());
+                if !is_root {
+                    return false;
+                }
+                self.active_root = Some(child);
+                true
+            }
+        }
+    }
+
+    /// Jump straight to `node`, wherever it sits in the tree - it doesn't have to be adjacent to
+    /// the current position. Doesn't change any branch's active child. Returns `false` if `node`
+    /// isn't in the history.
+    pub fn jump_to(&mut self, node: Entity) -> bool {
+        if !self.nodes.contains_key(&node) {
+            return false;
+        }
+        self.current = Some(node);
+        true
+    }
+
+    /// The chain of entries from the tree's root down to [`Self::last`], oldest first. Empty if
+    /// nothing has been performed yet.
+    pub fn path_from_root(&self) -> Vec<Entity> {
+        let mut path = Vec::new();
+        let mut node = self.current;
+        while let Some(entity) = node {
+            path.push(entity);
+            node = self.nodes.get(&entity).and_then(|n| n.parent);
+        }
+        path.reverse();
+        path
+    }
+
+    /// The entries [`Self::forward`] would walk through from here, following each node's active
+    /// child all the way to a leaf.
+    pub fn future_branch(&self) -> Vec<Entity> {
+        let mut future = Vec::new();
+        let mut node = match self.current {
+            Some(current) => self.nodes.get(&current).and_then(|n| n.active_child),
+            None => self.active_root,
+        };
+        while let Some(entity) = node {
+            future.push(entity);
+            node = self.nodes.get(&entity).and_then(|n| n.active_child);
+        }
+        future
+    }
+
+    /// Evict the single oldest surviving entry, by push order, until at or under `max_len`.
+    /// [`Self::last`] is the only entry this ever exempts - in an ordinary linear session that's
+    /// still the newest entry, so old ones keep getting evicted from the front as intended.
+    ///
+    /// Evicting an entry that still has tree children (an ancestor of [`Self::last`], or of some
+    /// other branch) re-roots those children rather than destroying them - each becomes the root
+    /// of its own subtree, same as if it had been pushed with no parent to begin with.
+    fn evict_over_capacity(&mut self) -> Vec<Entity> {
+        let Some(max_len) = self.max_len else {
+            return Vec::new();
+        };
+
+        let mut removed = Vec::new();
+
+        while self.nodes.len() > max_len {
+            // `order` is oldest-first, so the first entry that isn't the one we're sitting on
+            // is the oldest evictable entry
+            let Some(index) = self.order.iter().position(|&entity| Some(entity) != self.current)
+            else {
+                break;
+            };
+            let victim = self.order.remove(index);
+            let node = self.nodes.remove(&victim).unwrap();
+
+            // `push` always parents a new entry under whatever was `current` at the time, so a
+            // still-present parent is necessarily older than `victim` - impossible, since we
+            // just evicted the oldest surviving entry. Any parent `victim` had was evicted
+            // earlier, which already re-rooted `victim`, so `node.parent` needs no handling.
+            for &child in &node.children {
+                if let Some(child_node) = self.nodes.get_mut(&child) {
+                    child_node.parent = None;
+                }
+            }
+            if self.active_root == Some(victim) {
+                self.active_root = node.active_child;
+            }
+
+            removed.push(victim);
+        }
+
         removed
     }
 }
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone)]
 pub struct HistoryItem {
     undo: fn(&mut World, Entity),
     redo: fn(&mut World, Entity),
+    /// Sub-action entities making up this entry, in apply order. Empty for a plain action;
+    /// populated for the group entity spawned by [`Transaction::Commit`].
+    children: Vec<Entity>,
+    /// Tags which [`Action`] type produced this entry, so [`PerformAction`] can check whether
+    /// coalescing is type-safe before attempting [`Action::merge`].
+    type_id: TypeId,
 }
 
 impl<T: Action> FromType<T> for HistoryItem {
@@ -166,6 +576,8 @@ impl<T: Action> FromType<T> for HistoryItem {
                 action.apply(world);
                 *world.get_mut::<T>(entity).unwrap() = action;
             },
+            children: Vec::new(),
+            type_id: TypeId::of::<T>(),
         }
     }
 }
@@ -175,6 +587,50 @@ impl HistoryItem {
         FromType::<T>::from_type()
     }
 
+    /// Build a [`HistoryItem`] for a transaction: undoing it undoes `children` in reverse order,
+    /// redoing it redoes them in the order they were performed.
+    pub fn group(children: Vec<Entity>) -> Self {
+        Self {
+            undo: |world, entity| {
+                let Some(children) = world
+                    .get::<HistoryItem>(entity)
+                    .map(|item| item.children.clone())
+                else {
+                    warn!("{entity:?} has no HistoryItem, skipping group undo");
+                    return;
+                };
+                for child in children.into_iter().rev() {
+                    match world.get::<HistoryItem>(child).cloned() {
+                        Some(item) => item.undo(world, child),
+                        None => warn!("{child:?} has no HistoryItem, skipping"),
+                    }
+                }
+            },
+            redo: |world, entity| {
+                let Some(children) = world
+                    .get::<HistoryItem>(entity)
+                    .map(|item| item.children.clone())
+                else {
+                    warn!("{entity:?} has no HistoryItem, skipping group redo");
+                    return;
+                };
+                for child in children {
+                    match world.get::<HistoryItem>(child).cloned() {
+                        Some(item) => item.redo(world, child),
+                        None => warn!("{child:?} has no HistoryItem, skipping"),
+                    }
+                }
+            },
+            children,
+            // groups never coalesce, so this just needs to not collide with a real `Action`
+            type_id: TypeId::of::<()>(),
+        }
+    }
+
+    pub fn is_group(&self) -> bool {
+        !self.children.is_empty()
+    }
+
     pub fn undo(&self, world: &mut World, entity: Entity) {
         (self.undo)(world, entity);
     }
@@ -183,3 +639,130 @@ impl HistoryItem {
         (self.redo)(world, entity);
     }
 }
+
+/// Undo for free: wraps an arbitrary mutation and restores an entity's reflected components
+/// around it, instead of requiring a hand-written [`Action`] that exactly inverts itself.
+///
+/// The mutation closure runs once, on the first `apply`. From then on `undo`/`redo` just swap
+/// the entity between the snapshots taken right before and right after that mutation.
+#[derive(Component)]
+#[require(HistoryItem(HistoryItem::new::<Self>))]
+pub struct Snapshot {
+    entity: Entity,
+    mutate: Option<Box<dyn FnMut(&mut World) + Send + Sync>>,
+    before: Vec<(TypeId, Box<dyn Reflect>)>,
+    after: Vec<(TypeId, Box<dyn Reflect>)>,
+}
+
+impl Snapshot {
+    pub fn new(entity: Entity, mutate: impl FnMut(&mut World) + Send + Sync + 'static) -> Self {
+        Self {
+            entity,
+            mutate: Some(Box::new(mutate)),
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+
+    /// Clone every registered component on `entity` into a detached backing store. Components
+    /// with no `ReflectComponent` registration are skipped with a warning.
+    fn capture(world: &mut World, entity: Entity) -> Vec<(TypeId, Box<dyn Reflect>)> {
+        let Some(entity_ref) = world.get_entity(entity) else {
+            return Vec::new();
+        };
+
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        entity_ref
+            .archetype()
+            .components()
+            .filter_map(|component_id| {
+                let info = world.components().get_info(component_id)?;
+                let type_id = info.type_id()?;
+                let Some(reflect_component) = registry
+                    .get(type_id)
+                    .and_then(|registration| registration.data::<ReflectComponent>())
+                else {
+                    warn!("`{}` isn't type registered, skipping in snapshot", info.name());
+                    return None;
+                };
+                let value = reflect_component.reflect(entity_ref)?.clone_value();
+                Some((type_id, value))
+            })
+            .collect()
+    }
+
+    /// Restore a backing store taken by [`Self::capture`] onto `entity` - the exact inverse of
+    /// whatever changed `entity`'s component set since that capture, not just a value overwrite.
+    /// Registered components the entity has now but `components` doesn't (e.g. ones the wrapped
+    /// mutation inserted) are removed, not left dangling with a stale value; components with no
+    /// `ReflectComponent` registration are left as they are.
+    fn restore(world: &mut World, entity: Entity, components: &[(TypeId, Box<dyn Reflect>)]) {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        let extra: Vec<TypeId> = world
+            .get_entity(entity)
+            .map(|entity_ref| {
+                entity_ref
+                    .archetype()
+                    .components()
+                    .filter_map(|component_id| world.components().get_info(component_id)?.type_id())
+                    .filter(|type_id| !components.iter().any(|(id, _)| id == type_id))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut entity_mut = world.entity_mut(entity);
+
+        for type_id in extra {
+            if let Some(reflect_component) = registry
+                .get(type_id)
+                .and_then(|registration| registration.data::<ReflectComponent>())
+            {
+                reflect_component.remove(&mut entity_mut);
+            }
+        }
+
+        for (type_id, value) in components {
+            let Some(reflect_component) = registry
+                .get(*type_id)
+                .and_then(|registration| registration.data::<ReflectComponent>())
+            else {
+                continue;
+            };
+            reflect_component.apply_or_insert(&mut entity_mut, value.as_ref(), &registry);
+        }
+    }
+}
+
+impl Placeholder for Snapshot {
+    fn placeholder() -> Self {
+        Self {
+            entity: Entity::PLACEHOLDER,
+            mutate: None,
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+}
+
+impl Action for Snapshot {
+    fn apply(&mut self, world: &mut World) {
+        match self.mutate.take() {
+            // first run: snapshot the state the mutation is about to clobber, then run it
+            Some(mut mutate) => {
+                self.before = Self::capture(world, self.entity);
+                mutate(world);
+            }
+            // redo: the mutation already ran once, just reapply the state it left behind
+            None => Self::restore(world, self.entity, &self.after),
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        self.after = Self::capture(world, self.entity);
+        Self::restore(world, self.entity, &self.before);
+    }
+}
@@ -13,15 +13,22 @@ use bevy::{
     ui::{widget::TextFlags, ContentSize, FocusPolicy},
 };
 
-use bevy_playground::{Action, History, HistoryItem, PerformAction, Placeholder, Redo, Undo};
+use bevy_playground::{
+    rebuild_groups, Action, History, HistoryGroup, HistoryItem, HistoryNode, PerformAction,
+    Placeholder, Redo, Transaction, Undo,
+};
 
 const SCENE_FILE: &str = "scene.scn";
 
 // serialize these components
+// note: a `Transaction::Commit` group entity carries `HistoryGroup` precisely so it survives
+// here - `HistoryItem` itself isn't `Reflect` and has to be rebuilt after load, see
+// `rebuild_groups`
 const COMPONENT_FILTER: LazyLock<SceneFilter> = LazyLock::new(|| {
     SceneFilter::deny_all()
         .allow::<SetLevel>()
         .allow::<MoveEntity>()
+        .allow::<HistoryGroup>()
         .allow::<Player>()
         .allow::<LevelText>()
         .allow::<Transform>()
@@ -41,15 +48,26 @@ fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .init_resource::<Level>()
-        .init_resource::<History>()
-        .register_type::<(History, SetLevel, MoveEntity, Level, Player, LevelText)>()
+        .insert_resource(History::default().with_merge_window(10))
+        .register_type::<(
+            History,
+            HistoryNode,
+            HistoryGroup,
+            SetLevel,
+            MoveEntity,
+            Level,
+            Player,
+            LevelText,
+        )>()
         .add_systems(Startup, setup)
         .add_systems(
             Update,
             (
                 (
+                    rebuild_groups,
                     movement_input,
                     level_input,
+                    combo_input,
                     history_input,
                     save_load_input,
                     update_level_text,
@@ -74,8 +92,10 @@ fn setup(mut commands: Commands) {
         "
 wasd: move player
 1..=9: add level
+p: add level and move player up, as one undo step
 left arrow: undo action
 right arrow: redo action
+tab: cycle redo branch (after undoing then acting differently)
 i: save scene
 o: load scene
 "
@@ -166,6 +186,14 @@ impl Action for MoveEntity {
         let mut transform = world.get_mut::<Transform>(self.entity).unwrap();
         transform.translation -= self.delta;
     }
+
+    // let holding a movement key collapse into one undo step instead of one per frame
+    fn merge(&mut self, next: &Self) -> bool {
+        self.entity == next.entity && {
+            self.delta += next.delta;
+            true
+        }
+    }
 }
 
 impl Placeholder for MoveEntity {
@@ -186,24 +214,27 @@ impl MapEntities for MoveEntity {
 fn movement_input(
     mut commands: Commands,
     key: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
     player: Query<Entity, With<Player>>,
 ) {
-    let just_pressed_axis = |low, high| match (key.just_pressed(low), key.just_pressed(high)) {
+    // `pressed`, not `just_pressed`: holding a key keeps moving every frame, relying on
+    // `MoveEntity::merge` to fold the resulting stream of actions into one undo step
+    let held_axis = |low, high| match (key.pressed(low), key.pressed(high)) {
         (true, false) => -1.0,
         (false, true) => 1.0,
         (false, false) | (true, true) => 0.0,
     };
 
     let dir = Vec2 {
-        x: just_pressed_axis(KeyCode::KeyA, KeyCode::KeyD),
-        y: just_pressed_axis(KeyCode::KeyS, KeyCode::KeyW),
+        x: held_axis(KeyCode::KeyA, KeyCode::KeyD),
+        y: held_axis(KeyCode::KeyS, KeyCode::KeyW),
     };
 
     if dir != Vec2::ZERO {
         commands.add(PerformAction {
             action: MoveEntity {
                 entity: player.single(),
-                delta: (dir * 100.0).extend(0.0),
+                delta: (dir * 100.0 * time.delta_seconds()).extend(0.0),
             },
         });
     }
@@ -236,6 +267,35 @@ fn level_input(mut commands: Commands, key: Res<ButtonInput<KeyCode>>) {
     }
 }
 
+/// "add level + move player" as one atomic editor operation.
+fn combo_input(
+    mut commands: Commands,
+    key: Res<ButtonInput<KeyCode>>,
+    player: Query<Entity, With<Player>>,
+) {
+    if !key.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+
+    commands.add(Transaction::Begin);
+    commands.add(move |world: &mut World| {
+        let new_level = world.resource::<Level>().0 + 1;
+        Command::apply(
+            PerformAction {
+                action: SetLevel(new_level),
+            },
+            world,
+        );
+    });
+    commands.add(PerformAction {
+        action: MoveEntity {
+            entity: player.single(),
+            delta: Vec3::Y * 100.0,
+        },
+    });
+    commands.add(Transaction::Commit);
+}
+
 fn history_input(mut commands: Commands, key: Res<ButtonInput<KeyCode>>) {
     if key.just_pressed(KeyCode::ArrowLeft) {
         commands.add(Undo);
@@ -244,6 +304,26 @@ fn history_input(mut commands: Commands, key: Res<ButtonInput<KeyCode>>) {
     if key.just_pressed(KeyCode::ArrowRight) {
         commands.add(Redo);
     }
+
+    // undoing then acting opens a sibling branch rather than discarding the old one; cycle
+    // through the current node's branches instead of always redoing the most recent
+    if key.just_pressed(KeyCode::Tab) {
+        commands.add(|world: &mut World| {
+            let mut history = world.resource_mut::<History>();
+            let children = history.children();
+            if children.len() < 2 {
+                return;
+            }
+            let active = history.future_branch().first().copied();
+            let next = match active.and_then(|active| children.iter().position(|&c| c == active))
+            {
+                Some(i) => children[(i + 1) % children.len()],
+                None => children[0],
+            };
+            history.switch_branch(next);
+            info!("switched to branch {next:?}");
+        });
+    }
 }
 
 fn save_load_input(
@@ -274,6 +354,7 @@ fn debug_history(
     history: Option<Res<History>>,
     level: Option<Res<Level>>,
     query: Query<&SetLevel>,
+    history_items: Query<&HistoryItem>,
 ) {
     let Some((history, level)) = Option::zip(history, level) else {
         return;
@@ -283,25 +364,31 @@ fn debug_history(
         return;
     }
 
+    let describe = |entity| match query.get(entity) {
+        Ok(level) => format!("{} ", level.0),
+        Err(_) => match history_items.get(entity) {
+            Ok(item) if item.is_group() => "G ".to_string(),
+            _ => "* ".to_string(),
+        },
+    };
+
+    // the path to the current node, plus a marker if more than one redo branch leads on from it
+    // (only the active one is shown here)
+    let past = history.path_from_root();
+    let branches = history.children().len();
+
     print!("[ ");
-    for i in 0..history.index {
-        match query.get(history.items[i]) {
-            Ok(level) => print!("{} ", level.0),
-            Err(_) => print!("* "),
-        }
+    for entity in past {
+        print!("{}", describe(entity));
     }
-    match history.index < history.items.len() {
-        true => print!("[{}] ", level.0),
-        false => print!("[{}]", level.0),
+    match branches > 1 {
+        true => print!("[{}]({branches} branches) ", level.0),
+        false => print!("[{}] ", level.0),
     }
-
-    for i in history.index..history.items.len() {
-        match query.get(history.items[i]) {
-            Ok(level) => print!("{} ", level.0),
-            Err(_) => print!("* "),
-        }
+    for entity in history.future_branch() {
+        print!("{}", describe(entity));
     }
-    println!(" ]");
+    println!("]");
 }
 
 fn save_scene(world: &mut World) {